@@ -0,0 +1,74 @@
+use crate::memory_address::MemoryAddress;
+#[cfg(feature = "allocator-api2")]
+use allocator_api2::alloc::AllocError;
+#[cfg(not(feature = "allocator-api2"))]
+use std::alloc::AllocError;
+use std::num::NonZeroUsize;
+
+/// A memory allocator over `MemoryAddress`/`NonZeroUsize`, the foundation every allocator in this
+/// crate implements.
+///
+/// This is deliberately not `core::alloc::Allocator`: that trait works in terms of `Layout` and
+/// `NonNull<[u8]>`, which forces a zero-size special case and a fallible `Layout` construction at
+/// every call site. Working in terms of a `NonZeroUsize` size and a `NonZeroUsize`
+/// power-of-two alignment sidesteps both; `AsAllocator`/`AsGlobal` adapt an `impl Allocator` to
+/// the standard traits for callers that need them.
+pub trait Allocator {
+    /// Allocates `non_zero_size` bytes aligned to `non_zero_power_of_two_alignment`.
+    fn allocate(
+        &self,
+        non_zero_size: NonZeroUsize,
+        non_zero_power_of_two_alignment: NonZeroUsize,
+    ) -> Result<MemoryAddress, AllocError>;
+
+    /// Deallocates a block previously returned by `allocate` (or a reallocate method) with the
+    /// same size and alignment.
+    ///
+    /// Implementations that can only reclaim the most recently made allocation are expected to
+    /// silently no-op otherwise, exactly as `BumpAllocator` does.
+    fn deallocate(
+        &self,
+        non_zero_size: NonZeroUsize,
+        non_zero_power_of_two_alignment: NonZeroUsize,
+        current_memory: MemoryAddress,
+    );
+
+    /// Reallocates `current_memory` to a smaller `non_zero_new_size`, in place.
+    ///
+    /// Unlike `core::alloc::Allocator::shrink`, this can never fail: the worst case is simply
+    /// leaving the allocation's size unchanged.
+    fn shrinking_reallocate(
+        &self,
+        non_zero_new_size: NonZeroUsize,
+        non_zero_power_of_two_alignment: NonZeroUsize,
+        non_zero_current_size: NonZeroUsize,
+        current_memory: MemoryAddress,
+    ) -> Result<MemoryAddress, AllocError>;
+
+    /// Reallocates `current_memory` to a larger `non_zero_new_size`, in place if possible,
+    /// otherwise by allocating fresh and copying `non_zero_current_size` bytes across.
+    fn growing_reallocate(
+        &self,
+        non_zero_new_size: NonZeroUsize,
+        non_zero_power_of_two_alignment: NonZeroUsize,
+        non_zero_current_size: NonZeroUsize,
+        current_memory: MemoryAddress,
+    ) -> Result<MemoryAddress, AllocError>;
+
+    /// As `allocate()`, but also reports how many bytes are actually usable from the returned
+    /// pointer, not just the number requested.
+    ///
+    /// The default implementation reports exactly `non_zero_size` usable bytes. Implementations
+    /// that can cheaply work out how much room trails the allocation — `BumpAllocator`, say, up to
+    /// the end of its region — should override this so a `RawVec`-like caller can grow in
+    /// place without going back to the `MemorySource`.
+    #[inline(always)]
+    fn allocate_with_excess(
+        &self,
+        non_zero_size: NonZeroUsize,
+        non_zero_power_of_two_alignment: NonZeroUsize,
+    ) -> Result<(MemoryAddress, NonZeroUsize), AllocError> {
+        let allocation = self.allocate(non_zero_size, non_zero_power_of_two_alignment)?;
+        Ok((allocation, non_zero_size))
+    }
+}
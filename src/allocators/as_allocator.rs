@@ -0,0 +1,122 @@
+use crate::allocators::allocator::Allocator;
+use crate::memory_address::MemoryAddress;
+#[cfg(feature = "allocator-api2")]
+use allocator_api2::alloc::AllocError;
+#[cfg(feature = "allocator-api2")]
+use allocator_api2::alloc::Allocator as StdAllocator;
+#[cfg(not(feature = "allocator-api2"))]
+use std::alloc::AllocError;
+#[cfg(not(feature = "allocator-api2"))]
+use std::alloc::Allocator as StdAllocator;
+use std::alloc::Layout;
+use std::num::NonZeroUsize;
+use std::ptr::NonNull;
+
+/// Adapts any `Allocator` from this crate to the `core::alloc::Allocator` trait, so it can back
+/// `Vec::new_in`, `Box::new_in`, and the rest of the standard collections that are aware of
+/// `allocator_api`.
+///
+/// With the `allocator-api2` feature enabled this targets the `allocator-api2` crate's stable
+/// mirror of that trait instead of the nightly-only `core::alloc::Allocator`, so the suite still
+/// builds on stable Rust.
+#[derive(Debug)]
+pub struct AsAllocator<A: Allocator>(pub A);
+
+impl<A: Allocator> AsAllocator<A> {
+    #[inline(always)]
+    fn non_zero_layout(layout: Layout) -> (NonZeroUsize, NonZeroUsize) {
+        let non_zero_size =
+            NonZeroUsize::new(layout.size()).unwrap_or(unsafe { NonZeroUsize::new_unchecked(1) });
+        let non_zero_power_of_two_alignment =
+            unsafe { NonZeroUsize::new_unchecked(layout.align()) };
+        (non_zero_size, non_zero_power_of_two_alignment)
+    }
+
+    #[inline(always)]
+    fn to_slice(memory_address: MemoryAddress, size: usize) -> NonNull<[u8]> {
+        NonNull::slice_from_raw_parts(memory_address, size)
+    }
+
+    /// A dangling pointer aligned to `layout.align()`, for the zero-size-allocation case.
+    ///
+    /// `NonNull::dangling()` is only aligned to `1`; `core::alloc::Allocator` requires the
+    /// returned block be aligned to `layout.align()` even when it is empty, so an over-aligned
+    /// zero-size layout (e.g. `[u64; 0]`) needs this instead.
+    #[inline(always)]
+    fn dangling(layout: Layout) -> NonNull<u8> {
+        unsafe { NonNull::new_unchecked(layout.align() as *mut u8) }
+    }
+}
+
+unsafe impl<A: Allocator> StdAllocator for AsAllocator<A> {
+    #[inline(always)]
+    fn allocate(&self, layout: Layout) -> Result<NonNull<[u8]>, AllocError> {
+        if layout.size() == 0 {
+            return Ok(Self::to_slice(Self::dangling(layout), 0));
+        }
+
+        let (non_zero_size, non_zero_power_of_two_alignment) = Self::non_zero_layout(layout);
+        self.0
+            .allocate(non_zero_size, non_zero_power_of_two_alignment)
+            .map(|memory_address| Self::to_slice(memory_address, layout.size()))
+    }
+
+    #[inline(always)]
+    unsafe fn deallocate(&self, ptr: NonNull<u8>, layout: Layout) {
+        if layout.size() != 0 {
+            let (non_zero_size, non_zero_power_of_two_alignment) = Self::non_zero_layout(layout);
+            self.0
+                .deallocate(non_zero_size, non_zero_power_of_two_alignment, ptr)
+        }
+    }
+
+    #[inline(always)]
+    unsafe fn grow(
+        &self,
+        ptr: NonNull<u8>,
+        old_layout: Layout,
+        new_layout: Layout,
+    ) -> Result<NonNull<[u8]>, AllocError> {
+        if old_layout.size() == 0 {
+            return self.allocate(new_layout);
+        }
+
+        let (non_zero_current_size, _) = Self::non_zero_layout(old_layout);
+        let (non_zero_new_size, non_zero_power_of_two_alignment) =
+            Self::non_zero_layout(new_layout);
+
+        self.0
+            .growing_reallocate(
+                non_zero_new_size,
+                non_zero_power_of_two_alignment,
+                non_zero_current_size,
+                ptr,
+            )
+            .map(|memory_address| Self::to_slice(memory_address, new_layout.size()))
+    }
+
+    #[inline(always)]
+    unsafe fn shrink(
+        &self,
+        ptr: NonNull<u8>,
+        old_layout: Layout,
+        new_layout: Layout,
+    ) -> Result<NonNull<[u8]>, AllocError> {
+        if old_layout.size() == 0 {
+            return Ok(Self::to_slice(Self::dangling(new_layout), 0));
+        }
+
+        let (non_zero_current_size, non_zero_power_of_two_alignment) =
+            Self::non_zero_layout(old_layout);
+        let (non_zero_new_size, _) = Self::non_zero_layout(new_layout);
+
+        self.0
+            .shrinking_reallocate(
+                non_zero_new_size,
+                non_zero_power_of_two_alignment,
+                non_zero_current_size,
+                ptr,
+            )
+            .map(|memory_address| Self::to_slice(memory_address, new_layout.size()))
+    }
+}
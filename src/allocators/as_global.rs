@@ -0,0 +1,102 @@
+use crate::allocators::allocator::Allocator;
+use std::alloc::GlobalAlloc;
+use std::alloc::Layout;
+use std::num::NonZeroUsize;
+use std::ptr::null_mut;
+use std::ptr::NonNull;
+
+/// Adapts any `Allocator` from this crate so it can be registered as the process's
+/// `#[global_allocator]`.
+///
+/// `Layout`'s `size` and `align` are translated into this crate's `(non_zero_size,
+/// non_zero_power_of_two_alignment)` pair; a zero-size `Layout` is translated to a dangling,
+/// suitably aligned pointer rather than an actual allocation, matching what `GlobalAlloc` callers
+/// expect.
+#[derive(Debug)]
+pub struct AsGlobal<A: Allocator>(pub A);
+
+impl<A: Allocator> AsGlobal<A> {
+    #[inline(always)]
+    fn non_zero_layout(layout: Layout) -> Option<(NonZeroUsize, NonZeroUsize)> {
+        let non_zero_size = NonZeroUsize::new(layout.size())?;
+        let non_zero_power_of_two_alignment =
+            unsafe { NonZeroUsize::new_unchecked(layout.align()) };
+        Some((non_zero_size, non_zero_power_of_two_alignment))
+    }
+
+    #[inline(always)]
+    fn dangling(layout: Layout) -> *mut u8 {
+        layout.align() as *mut u8
+    }
+}
+
+unsafe impl<A: Allocator> GlobalAlloc for AsGlobal<A> {
+    #[inline(always)]
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        match Self::non_zero_layout(layout) {
+            None => Self::dangling(layout),
+            Some((non_zero_size, non_zero_power_of_two_alignment)) => self
+                .0
+                .allocate(non_zero_size, non_zero_power_of_two_alignment)
+                .map(|memory_address| memory_address.as_ptr())
+                .unwrap_or(null_mut()),
+        }
+    }
+
+    #[inline(always)]
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        if let Some((non_zero_size, non_zero_power_of_two_alignment)) =
+            Self::non_zero_layout(layout)
+        {
+            self.0.deallocate(
+                non_zero_size,
+                non_zero_power_of_two_alignment,
+                NonNull::new_unchecked(ptr),
+            )
+        }
+    }
+
+    #[inline(always)]
+    unsafe fn realloc(&self, ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+        let current_memory = NonNull::new_unchecked(ptr);
+        let non_zero_power_of_two_alignment = NonZeroUsize::new_unchecked(layout.align());
+
+        let result = match NonZeroUsize::new(new_size) {
+            None => {
+                if let Some((non_zero_current_size, _)) = Self::non_zero_layout(layout) {
+                    self.0.deallocate(
+                        non_zero_current_size,
+                        non_zero_power_of_two_alignment,
+                        current_memory,
+                    )
+                }
+                return Self::dangling(layout);
+            }
+
+            Some(non_zero_new_size) => {
+                let non_zero_current_size =
+                    NonZeroUsize::new(layout.size()).unwrap_or(non_zero_new_size);
+
+                if new_size > layout.size() {
+                    self.0.growing_reallocate(
+                        non_zero_new_size,
+                        non_zero_power_of_two_alignment,
+                        non_zero_current_size,
+                        current_memory,
+                    )
+                } else {
+                    self.0.shrinking_reallocate(
+                        non_zero_new_size,
+                        non_zero_power_of_two_alignment,
+                        non_zero_current_size,
+                        current_memory,
+                    )
+                }
+            }
+        };
+
+        result
+            .map(|memory_address| memory_address.as_ptr())
+            .unwrap_or(null_mut())
+    }
+}
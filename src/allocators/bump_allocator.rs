@@ -6,9 +6,15 @@ use crate::extensions::non_zero_usize::non_zero_usize;
 use crate::extensions::prelude::*;
 use crate::memory_address::MemoryAddress;
 use crate::memory_sources::memory_source::MemorySource;
+#[cfg(feature = "allocator-api2")]
+use allocator_api2::alloc::AllocError;
+#[cfg(not(feature = "allocator-api2"))]
 use std::alloc::AllocError;
 use std::cell::Cell;
 use std::fmt::Debug;
+#[cfg(feature = "allocator-api2")]
+use std::mem::transmute;
+#[cfg(not(feature = "allocator-api2"))]
 use std::intrinsics::transmute;
 use std::num::NonZeroUsize;
 
@@ -43,6 +49,16 @@ impl<MS: MemorySource> Drop for BumpAllocator<MS> {
     }
 }
 
+// `core::intrinsics::unlikely` (reached via `crate::extensions::prelude::*`) is nightly-only;
+// under the `allocator-api2` stable path there is no branch-prediction hint to give the
+// compiler, so this shadows it with a no-op expansion of the bare condition.
+#[cfg(feature = "allocator-api2")]
+macro_rules! unlikely {
+    ($condition:expr) => {
+        $condition
+    };
+}
+
 macro_rules! allocation_ends_at_pointer
 {
 	($self: ident, $non_zero_size: ident, $allocation_from: ident) =>
@@ -159,6 +175,31 @@ impl<MS: MemorySource> Allocator for BumpAllocator<MS> {
             }
         }
     }
+
+    /// As `allocate()`, but also reports how many bytes are actually usable from the returned
+    /// pointer, not just the number requested.
+    ///
+    /// Since this allocator only ever bumps forward, the usable length is everything between the
+    /// allocation and `ends_at_pointer`; a `RawVec`-like caller can use that slack to grow in
+    /// place without going back to the `MemorySource`. The excess is not a separately reserved
+    /// region: making another allocation will bump `next_allocation_at_pointer` straight through
+    /// it, so callers must not write into it after allocating again.
+    #[inline(always)]
+    fn allocate_with_excess(
+        &self,
+        non_zero_size: NonZeroUsize,
+        non_zero_power_of_two_alignment: NonZeroUsize,
+    ) -> Result<(MemoryAddress, NonZeroUsize), AllocError> {
+        let allocation = self.allocate(non_zero_size, non_zero_power_of_two_alignment)?;
+
+        let usable_size = unsafe {
+            NonZeroUsize::new_unchecked(
+                self.ends_at_pointer.as_ptr() as usize - allocation.as_ptr() as usize,
+            )
+        };
+
+        Ok((allocation, usable_size))
+    }
 }
 
 impl<MS: MemorySource> LocalAllocator for BumpAllocator<MS> {
@@ -0,0 +1,333 @@
+use crate::allocators::allocator::Allocator;
+
+use crate::extensions::non_zero_usize::non_zero_usize;
+use crate::extensions::prelude::*;
+use crate::memory_address::MemoryAddress;
+use crate::memory_sources::memory_source::MemorySource;
+#[cfg(feature = "allocator-api2")]
+use allocator_api2::alloc::AllocError;
+#[cfg(not(feature = "allocator-api2"))]
+use std::alloc::AllocError;
+use std::cell::Cell;
+use std::fmt::Debug;
+#[cfg(feature = "allocator-api2")]
+use std::mem::transmute;
+#[cfg(not(feature = "allocator-api2"))]
+use std::intrinsics::transmute;
+use std::mem::align_of;
+use std::mem::size_of;
+use std::num::NonZeroUsize;
+
+/// A footer written at the high end of every chunk obtained from the `MemorySource`.
+///
+/// Chaining chunks by a footer rather than a header means a chunk's data starts exactly at the
+/// pointer the `MemorySource` handed back, with no header-sized offset to account for.
+#[derive(Debug)]
+struct ChunkFooter {
+    prev_chunk: Option<MemoryAddress>,
+    chunk_size: NonZeroUsize,
+    cursor: Cell<MemoryAddress>,
+}
+
+/// A bump allocator that, unlike `BumpAllocator`, keeps growing: when the current chunk is
+/// exhausted it obtains a new, larger chunk from the `MemorySource` and chains it to the old one
+/// via a `ChunkFooter` rather than failing with `AllocError`.
+///
+/// It:-
+///
+/// * Can efficiently shrink and grow (reallocate) for the most recent allocation made, exactly as
+///   `BumpAllocator` does, as long as that allocation has not crossed into a new chunk.
+/// * Supports `reset()`, an O(1) bulk reclamation that releases every chunk except the largest and
+///   rewinds it, suited to arena-style workloads (parse a request, reset, repeat).
+/// * Has no ability to resize in place if dead space occurs before the next allocation because of
+///   alignment, the same limitation `BumpAllocator` has.
+///
+/// Is suitable for the same short-lived coroutine use case as `BumpAllocator`, but for workloads
+/// whose peak size is not known up front.
+///
+/// This allocator is not thread-safe.
+#[derive(Debug)]
+pub struct ChunkedBumpAllocator<MS: MemorySource> {
+    most_recent_allocation_pointer: Cell<MemoryAddress>,
+    current_chunk_footer: Cell<MemoryAddress>,
+
+    memory_source: MS,
+}
+
+impl<MS: MemorySource> Drop for ChunkedBumpAllocator<MS> {
+    #[inline(always)]
+    fn drop(&mut self) {
+        let mut chunk = Some(self.current_chunk_footer.get());
+        while let Some(footer_address) = chunk {
+            let footer = Self::footer(footer_address);
+            self.memory_source
+                .release(footer.chunk_size, Self::chunk_base(footer_address, footer.chunk_size));
+            chunk = footer.prev_chunk;
+        }
+    }
+}
+
+// `core::intrinsics::unlikely` (reached via `crate::extensions::prelude::*`) is nightly-only;
+// under the `allocator-api2` stable path there is no branch-prediction hint to give the
+// compiler, so this shadows it with a no-op expansion of the bare condition.
+#[cfg(feature = "allocator-api2")]
+macro_rules! unlikely {
+    ($condition:expr) => {
+        $condition
+    };
+}
+
+impl<MS: MemorySource> Allocator for ChunkedBumpAllocator<MS> {
+    #[inline(always)]
+    fn allocate(
+        &self,
+        non_zero_size: NonZeroUsize,
+        non_zero_power_of_two_alignment: NonZeroUsize,
+    ) -> Result<MemoryAddress, AllocError> {
+        debug_assert!(
+            non_zero_power_of_two_alignment <= Self::MAXIMUM_POWER_OF_TWO_ALIGNMENT,
+            "non_zero_power_of_two_alignment `{}` exceeds `{}`",
+            non_zero_power_of_two_alignment,
+            Self::MAXIMUM_POWER_OF_TWO_ALIGNMENT
+        );
+
+        loop {
+            let footer_address = self.current_chunk_footer.get();
+            let footer = Self::footer(footer_address);
+
+            let rounded_up_pointer = footer
+                .cursor
+                .get()
+                .round_up_to_power_of_two(non_zero_power_of_two_alignment);
+
+            if let Some(allocation_ends_at_pointer) =
+                rounded_up_pointer.checked_add(non_zero_size.get())
+            {
+                if !unlikely!(allocation_ends_at_pointer > footer_address) {
+                    footer.cursor.set(allocation_ends_at_pointer);
+                    self.most_recent_allocation_pointer
+                        .set(rounded_up_pointer);
+                    return Ok(rounded_up_pointer);
+                }
+            }
+
+            self.obtain_next_chunk(
+                non_zero_size,
+                non_zero_power_of_two_alignment,
+                footer.chunk_size,
+                footer_address,
+            )?;
+        }
+    }
+
+    #[inline(always)]
+    fn deallocate(
+        &self,
+        _non_zero_size: NonZeroUsize,
+        _non_zero_power_of_two_alignment: NonZeroUsize,
+        current_memory: MemoryAddress,
+    ) {
+        if unlikely!(current_memory == self.most_recent_allocation_pointer.get()) {
+            Self::footer(self.current_chunk_footer.get())
+                .cursor
+                .set(current_memory)
+        }
+    }
+
+    #[inline(always)]
+    fn shrinking_reallocate(
+        &self,
+        non_zero_new_size: NonZeroUsize,
+        _non_zero_power_of_two_alignment: NonZeroUsize,
+        _non_zero_current_size: NonZeroUsize,
+        current_memory: MemoryAddress,
+    ) -> Result<MemoryAddress, AllocError> {
+        if unlikely!(current_memory == self.most_recent_allocation_pointer.get()) {
+            Self::footer(self.current_chunk_footer.get())
+                .cursor
+                .set(current_memory.add(non_zero_new_size.get()))
+        }
+
+        Ok(current_memory)
+    }
+
+    #[inline(always)]
+    fn growing_reallocate(
+        &self,
+        non_zero_new_size: NonZeroUsize,
+        non_zero_power_of_two_alignment: NonZeroUsize,
+        non_zero_current_size: NonZeroUsize,
+        current_memory: MemoryAddress,
+    ) -> Result<MemoryAddress, AllocError> {
+        let footer_address = self.current_chunk_footer.get();
+        let footer = Self::footer(footer_address);
+
+        if unlikely!(current_memory == self.most_recent_allocation_pointer.get()) {
+            if let Some(allocation_ends_at_pointer) =
+                current_memory.checked_add(non_zero_new_size.get())
+            {
+                if !unlikely!(allocation_ends_at_pointer > footer_address) {
+                    footer.cursor.set(allocation_ends_at_pointer);
+                    return Ok(current_memory);
+                }
+            }
+        }
+
+        let result = self.allocate(non_zero_new_size, non_zero_power_of_two_alignment);
+        let pointer: *mut u8 = unsafe { transmute(result) };
+        if unlikely!(pointer.is_null()) {
+            Err(AllocError)
+        } else {
+            let current_size = non_zero_current_size.get();
+            unsafe { pointer.copy_from(current_memory.as_ptr(), current_size) };
+            Ok(unsafe { transmute(pointer) })
+        }
+    }
+}
+
+impl<MS: MemorySource> ChunkedBumpAllocator<MS> {
+    const MAXIMUM_POWER_OF_TWO_ALIGNMENT: NonZeroUsize = non_zero_usize(4096);
+
+    /// New instance, obtaining an initial chunk of `initial_chunk_size` from `memory_source`.
+    #[inline(always)]
+    pub fn new(memory_source: MS, initial_chunk_size: NonZeroUsize) -> Result<Self, AllocError> {
+        let footer_address = Self::obtain_chunk(&memory_source, initial_chunk_size, None)?;
+
+        Ok(Self {
+            most_recent_allocation_pointer: Cell::new(footer_address),
+            current_chunk_footer: Cell::new(footer_address),
+            memory_source,
+        })
+    }
+
+    /// Releases every chunk except the single largest one, then rewinds that chunk's cursor to
+    /// its base; an O(1) bulk reclamation suited to arena-style workloads (parse a request,
+    /// reset, repeat) instead of per-allocation `deallocate`.
+    #[inline(always)]
+    pub fn reset(&self) {
+        let mut largest_footer_address = self.current_chunk_footer.get();
+        let mut largest_chunk_size = Self::footer(largest_footer_address).chunk_size;
+
+        let mut chunk = Some(largest_footer_address);
+        while let Some(footer_address) = chunk {
+            let footer = Self::footer(footer_address);
+            if footer.chunk_size > largest_chunk_size {
+                largest_chunk_size = footer.chunk_size;
+                largest_footer_address = footer_address;
+            }
+            chunk = footer.prev_chunk;
+        }
+
+        let mut chunk = Some(self.current_chunk_footer.get());
+        while let Some(footer_address) = chunk {
+            let footer = Self::footer(footer_address);
+            let prev_chunk = footer.prev_chunk;
+            if footer_address != largest_footer_address {
+                self.memory_source.release(
+                    footer.chunk_size,
+                    Self::chunk_base(footer_address, footer.chunk_size),
+                );
+            }
+            chunk = prev_chunk;
+        }
+
+        let chunk_base = Self::chunk_base(largest_footer_address, largest_chunk_size);
+        Self::write_footer(
+            largest_footer_address,
+            ChunkFooter {
+                prev_chunk: None,
+                chunk_size: largest_chunk_size,
+                cursor: Cell::new(chunk_base),
+            },
+        );
+
+        self.current_chunk_footer.set(largest_footer_address);
+        self.most_recent_allocation_pointer.set(chunk_base);
+    }
+
+    #[inline(always)]
+    fn footer_size() -> NonZeroUsize {
+        non_zero_usize(size_of::<ChunkFooter>())
+    }
+
+    #[inline(always)]
+    fn footer(footer_address: MemoryAddress) -> &'static ChunkFooter {
+        unsafe { footer_address.cast::<ChunkFooter>().as_ref() }
+    }
+
+    #[inline(always)]
+    fn write_footer(footer_address: MemoryAddress, footer: ChunkFooter) {
+        unsafe { footer_address.cast::<ChunkFooter>().as_ptr().write(footer) }
+    }
+
+    #[inline(always)]
+    fn chunk_base(footer_address: MemoryAddress, chunk_size: NonZeroUsize) -> MemoryAddress {
+        footer_address
+            .add_non_zero(Self::footer_size())
+            .subtract_non_zero(chunk_size)
+    }
+
+    /// Obtains a new chunk sized at least `max(non_zero_size rounded up for the footer and
+    /// alignment slack, previous_chunk_size * 2)`, writes its footer linking it back to
+    /// `prev_chunk_footer_address`, and makes it current.
+    ///
+    /// `non_zero_power_of_two_alignment` is added to the requested size because `allocate` does
+    /// not assume a fresh `chunk_base` is aligned to more than the `MemorySource`'s own guarantee:
+    /// without the slack, a base less aligned than the request could round the cursor past
+    /// `footer_address` and force an immediate, wasted second `obtain_next_chunk` round.
+    #[inline(always)]
+    fn obtain_next_chunk(
+        &self,
+        non_zero_size: NonZeroUsize,
+        non_zero_power_of_two_alignment: NonZeroUsize,
+        previous_chunk_size: NonZeroUsize,
+        prev_chunk_footer_address: MemoryAddress,
+    ) -> Result<(), AllocError> {
+        let requested = non_zero_size
+            .get()
+            .saturating_add(non_zero_power_of_two_alignment.get())
+            .saturating_add(Self::footer_size().get());
+        let doubled = previous_chunk_size.get().saturating_mul(2);
+        let new_chunk_size = non_zero_usize(requested.max(doubled).next_power_of_two());
+
+        let footer_address =
+            Self::obtain_chunk(&self.memory_source, new_chunk_size, Some(prev_chunk_footer_address))?;
+        self.current_chunk_footer.set(footer_address);
+        Ok(())
+    }
+
+    /// Obtains a chunk of (at least) `chunk_size` bytes and writes its footer at the high end.
+    ///
+    /// `chunk_size` is rounded up to a multiple of `align_of::<ChunkFooter>()` first: the footer
+    /// is placed at `chunk_base + chunk_size - footer_size`, and this is only guaranteed to be
+    /// correctly aligned for `ChunkFooter` if `chunk_size` is itself a multiple of that alignment
+    /// (this assumes, as every `MemorySource` in this crate does, that `chunk_base` is at least
+    /// as aligned as `ChunkFooter` requires).
+    #[inline(always)]
+    fn obtain_chunk(
+        memory_source: &MS,
+        chunk_size: NonZeroUsize,
+        prev_chunk: Option<MemoryAddress>,
+    ) -> Result<MemoryAddress, AllocError> {
+        let chunk_size = Self::round_up_to_footer_alignment(chunk_size);
+        let chunk_base = memory_source.obtain(chunk_size)?;
+        let footer_address = chunk_base.add_non_zero(chunk_size).subtract_non_zero(Self::footer_size());
+
+        Self::write_footer(
+            footer_address,
+            ChunkFooter {
+                prev_chunk,
+                chunk_size,
+                cursor: Cell::new(chunk_base),
+            },
+        );
+
+        Ok(footer_address)
+    }
+
+    #[inline(always)]
+    fn round_up_to_footer_alignment(chunk_size: NonZeroUsize) -> NonZeroUsize {
+        let align = align_of::<ChunkFooter>();
+        non_zero_usize((chunk_size.get() + align - 1) & !(align - 1))
+    }
+}
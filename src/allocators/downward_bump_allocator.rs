@@ -0,0 +1,233 @@
+use crate::allocators::allocator::Allocator;
+use crate::allocators::global::local_allocator::LocalAllocator;
+use crate::allocators::global::memory_range::MemoryRange;
+
+use crate::extensions::non_zero_usize::non_zero_usize;
+use crate::extensions::prelude::*;
+use crate::memory_address::MemoryAddress;
+use crate::memory_sources::memory_source::MemorySource;
+#[cfg(feature = "allocator-api2")]
+use allocator_api2::alloc::AllocError;
+#[cfg(not(feature = "allocator-api2"))]
+use std::alloc::AllocError;
+use std::cell::Cell;
+use std::fmt::Debug;
+#[cfg(feature = "allocator-api2")]
+use std::mem::transmute;
+#[cfg(not(feature = "allocator-api2"))]
+use std::intrinsics::transmute;
+use std::num::NonZeroUsize;
+
+/// A bump allocator that bumps its cursor *downward*, from the end of its region towards the
+/// start, rather than the upward direction `BumpAllocator` uses.
+///
+/// Bumping downward collapses the alignment-rounding and the bounds check into a single mask-and-
+/// compare instead of a round-up-then-add sequence, giving a measurably tighter hot path; this is
+/// the same trick bumpalo and similar arenas use.
+///
+/// It:-
+///
+/// * Shrinks any allocation in place, always successfully, but without reclaiming the freed
+///   bytes; see the last point below.
+/// * Can grow the most recent allocation in place (without going back to the `MemorySource`), but
+///   unlike `BumpAllocator` this always shifts the allocation's bytes, because the block's lowest
+///   address is the one that moves; see `growing_reallocate`.
+/// * Has no wrapping around at the end (but this could be achieved using a mirror ring buffer).
+/// * Has no ability to resize in place if dead space occurs after the most recent allocation
+///   because of alignment, and (unlike `BumpAllocator`) shrinking can never reclaim its freed tail
+///   either, because that tail sits on the side away from free space; see `shrinking_reallocate`.
+///
+/// Is suitable for use with short-lived coroutines, such as those used to make a DNS query.
+///
+/// This allocator NEVER grows or shrinks its memory region.
+///
+/// This allocator is not thread-safe.
+#[derive(Debug)]
+pub struct DownwardBumpAllocator<MS: MemorySource> {
+    most_recent_allocation_pointer: Cell<MemoryAddress>,
+    next_allocation_at_pointer: Cell<MemoryAddress>,
+    starts_at_pointer: MemoryAddress,
+
+    memory_source: MS,
+    memory_source_size: NonZeroUsize,
+}
+
+impl<MS: MemorySource> Drop for DownwardBumpAllocator<MS> {
+    #[inline(always)]
+    fn drop(&mut self) {
+        self.memory_source
+            .release(self.memory_source_size, self.starts_at_pointer)
+    }
+}
+
+// `core::intrinsics::unlikely` (reached via `crate::extensions::prelude::*`) is nightly-only;
+// under the `allocator-api2` stable path there is no branch-prediction hint to give the
+// compiler, so this shadows it with a no-op expansion of the bare condition.
+#[cfg(feature = "allocator-api2")]
+macro_rules! unlikely {
+    ($condition:expr) => {
+        $condition
+    };
+}
+
+impl<MS: MemorySource> Allocator for DownwardBumpAllocator<MS> {
+    #[inline(always)]
+    fn allocate(
+        &self,
+        non_zero_size: NonZeroUsize,
+        non_zero_power_of_two_alignment: NonZeroUsize,
+    ) -> Result<MemoryAddress, AllocError> {
+        debug_assert!(
+            non_zero_power_of_two_alignment <= Self::MAXIMUM_POWER_OF_TWO_ALIGNMENT,
+            "non_zero_power_of_two_alignment `{}` exceeds `{}`",
+            non_zero_power_of_two_alignment,
+            Self::MAXIMUM_POWER_OF_TWO_ALIGNMENT
+        );
+
+        let candidate = match Self::round_down_candidate(
+            self.next_allocation_at_pointer.get(),
+            non_zero_size,
+            non_zero_power_of_two_alignment,
+        ) {
+            Some(candidate) => candidate,
+            None => return Err(AllocError),
+        };
+
+        if unlikely!(Self::address_of(candidate) < Self::address_of(self.starts_at_pointer)) {
+            return Err(AllocError);
+        }
+
+        self.most_recent_allocation_pointer.set(candidate);
+        self.next_allocation_at_pointer.set(candidate);
+
+        Ok(candidate)
+    }
+
+    #[inline(always)]
+    fn deallocate(
+        &self,
+        non_zero_size: NonZeroUsize,
+        _non_zero_power_of_two_alignment: NonZeroUsize,
+        current_memory: MemoryAddress,
+    ) {
+        // Unlike `BumpAllocator::deallocate`, `non_zero_size` is needed here: freeing the most
+        // recent (lowest-addressed) allocation reclaims space by moving the cursor back up to
+        // the end of that allocation. Any alignment padding taken above it when it was made is
+        // not recovered, since this allocator does not remember where the cursor was before.
+        if unlikely!(current_memory == self.most_recent_allocation_pointer.get()) {
+            self.next_allocation_at_pointer
+                .set(current_memory.add(non_zero_size.get()))
+        }
+    }
+
+    #[inline(always)]
+    fn shrinking_reallocate(
+        &self,
+        _non_zero_new_size: NonZeroUsize,
+        _non_zero_power_of_two_alignment: NonZeroUsize,
+        _non_zero_current_size: NonZeroUsize,
+        current_memory: MemoryAddress,
+    ) -> Result<MemoryAddress, AllocError> {
+        // Unlike the upward variant, shrinking never reclaims space here: the freed tail sits on
+        // the high side of the allocation, which is where an older allocation (or nothing
+        // useful) lives, not where the free region is (the free region is below the lowest live
+        // allocation). The bytes are simply abandoned, in the same spirit as the alignment dead
+        // space `BumpAllocator` already cannot reclaim.
+        Ok(current_memory)
+    }
+
+    #[inline(always)]
+    fn growing_reallocate(
+        &self,
+        non_zero_new_size: NonZeroUsize,
+        non_zero_power_of_two_alignment: NonZeroUsize,
+        non_zero_current_size: NonZeroUsize,
+        current_memory: MemoryAddress,
+    ) -> Result<MemoryAddress, AllocError> {
+        if unlikely!(current_memory == self.most_recent_allocation_pointer.get()) {
+            let allocation_ends_at_pointer = current_memory.add(non_zero_current_size.get());
+
+            if let Some(candidate) = Self::round_down_candidate(
+                allocation_ends_at_pointer,
+                non_zero_new_size,
+                non_zero_power_of_two_alignment,
+            ) {
+                if !unlikely!(Self::address_of(candidate) < Self::address_of(self.starts_at_pointer))
+                {
+                    self.most_recent_allocation_pointer.set(candidate);
+                    self.next_allocation_at_pointer.set(candidate);
+
+                    // The lowest live address has just moved down to make room; the existing
+                    // bytes are shifted to meet it. The source and destination can overlap when
+                    // the growth is smaller than the current size, so this must be a `copy_from`
+                    // (memmove), not a nonoverlapping copy.
+                    unsafe {
+                        candidate
+                            .as_ptr()
+                            .copy_from(current_memory.as_ptr(), non_zero_current_size.get())
+                    };
+
+                    return Ok(candidate);
+                }
+            }
+        }
+
+        let result = self.allocate(non_zero_new_size, non_zero_power_of_two_alignment);
+        let pointer: *mut u8 = unsafe { transmute(result) };
+        if unlikely!(pointer.is_null()) {
+            Err(AllocError)
+        } else {
+            let current_size = non_zero_current_size.get();
+            unsafe { pointer.copy_from(current_memory.as_ptr(), current_size) };
+            Ok(unsafe { transmute(pointer) })
+        }
+    }
+}
+
+impl<MS: MemorySource> LocalAllocator for DownwardBumpAllocator<MS> {
+    #[inline(always)]
+    fn memory_range(&self) -> MemoryRange {
+        MemoryRange::new(
+            self.starts_at_pointer,
+            self.starts_at_pointer.add_non_zero(self.memory_source_size),
+        )
+    }
+}
+
+impl<MS: MemorySource> DownwardBumpAllocator<MS> {
+    const MAXIMUM_POWER_OF_TWO_ALIGNMENT: NonZeroUsize = non_zero_usize(4096);
+
+    /// New instance wrapping a block of memory.
+    #[inline(always)]
+    pub fn new(memory_source: MS, memory_source_size: NonZeroUsize) -> Result<Self, AllocError> {
+        let starts_at_pointer = memory_source.obtain(memory_source_size)?;
+        let ends_at_pointer = starts_at_pointer.add_non_zero(memory_source_size);
+
+        Ok(Self {
+            most_recent_allocation_pointer: Cell::new(ends_at_pointer),
+            next_allocation_at_pointer: Cell::new(ends_at_pointer),
+            starts_at_pointer,
+
+            memory_source,
+            memory_source_size,
+        })
+    }
+
+    /// Computes `(upper_bound - non_zero_size) & !(non_zero_power_of_two_alignment - 1)` as a
+    /// single mask, returning `None` on underflow rather than wrapping.
+    #[inline(always)]
+    fn round_down_candidate(
+        upper_bound: MemoryAddress,
+        non_zero_size: NonZeroUsize,
+        non_zero_power_of_two_alignment: NonZeroUsize,
+    ) -> Option<MemoryAddress> {
+        let subtracted = Self::address_of(upper_bound).checked_sub(non_zero_size.get())?;
+        let masked = subtracted & !(non_zero_power_of_two_alignment.get() - 1);
+        Some(unsafe { transmute(masked as *mut u8) })
+    }
+
+    #[inline(always)]
+    fn address_of(memory_address: MemoryAddress) -> usize {
+        memory_address.as_ptr() as usize
+    }
+}
@@ -0,0 +1,259 @@
+use crate::allocators::allocator::Allocator;
+use crate::allocators::global::local_allocator::LocalAllocator;
+use crate::allocators::global::memory_range::MemoryRange;
+
+use crate::extensions::non_zero_usize::non_zero_usize;
+use crate::extensions::prelude::*;
+use crate::memory_address::MemoryAddress;
+use crate::memory_sources::memory_source::MemorySource;
+#[cfg(feature = "allocator-api2")]
+use allocator_api2::alloc::AllocError;
+#[cfg(not(feature = "allocator-api2"))]
+use std::alloc::AllocError;
+use std::cell::Cell;
+use std::fmt::Debug;
+#[cfg(feature = "allocator-api2")]
+use std::mem::transmute;
+#[cfg(not(feature = "allocator-api2"))]
+use std::intrinsics::transmute;
+use std::num::NonZeroUsize;
+
+/// A bump allocator over a ring buffer, for streaming workloads that reuse the same region
+/// indefinitely (e.g. reusing one buffer across many successive DNS queries) without ever
+/// releasing and re-obtaining memory from the `MemorySource`.
+///
+/// The `MemorySource` passed to `new` must map its physical pages twice, at consecutive virtual
+/// addresses (the standard double-mapping trick used to back a ring buffer), so that an
+/// allocation whose logical offset is close to `capacity` is still contiguous in the address
+/// space instead of having to be split at the wrap point.
+///
+/// It:-
+///
+/// * Can efficiently shrink and grow (reallocate) for the most recent allocation made, as
+///   `BumpAllocator` does.
+/// * Bumps modulo `capacity`; when an allocation would make the cursor lap data from this session
+///   that has not yet been reclaimed by `reset()`, it returns `AllocError` rather than silently
+///   overwriting it.
+/// * Is recycled with `reset()`, an O(1) operation that makes the whole capacity available again
+///   without calling into the `MemorySource`; there is no per-chunk release to do, since this
+///   allocator only ever owns the one region.
+///
+/// Is suitable for the same short-lived coroutine use case as `BumpAllocator`, but for producers
+/// that keep resetting and reusing a single buffer rather than obtaining a fresh one each time.
+///
+/// This allocator is not thread-safe.
+#[derive(Debug)]
+pub struct MirroredRingBumpAllocator<MS: MemorySource> {
+    most_recent_allocation_pointer: Cell<MemoryAddress>,
+    most_recent_allocation_size: Cell<usize>,
+    // The pre-rounding position and the alignment padding `allocate` charged to `used` for the
+    // most recent allocation, so `deallocate` can credit it back exactly
+    // instead of letting it leak from `used`/`position` on every alloc/free cycle that pads.
+    most_recent_allocation_started_at: Cell<usize>,
+    most_recent_allocation_padding: Cell<usize>,
+    position: Cell<usize>,
+    used: Cell<usize>,
+
+    base: MemoryAddress,
+    capacity: NonZeroUsize,
+    memory_source: MS,
+}
+
+impl<MS: MemorySource> Drop for MirroredRingBumpAllocator<MS> {
+    #[inline(always)]
+    fn drop(&mut self) {
+        self.memory_source.release(self.capacity, self.base)
+    }
+}
+
+// `core::intrinsics::unlikely` (reached via `crate::extensions::prelude::*`) is nightly-only;
+// under the `allocator-api2` stable path there is no branch-prediction hint to give the
+// compiler, so this shadows it with a no-op expansion of the bare condition.
+#[cfg(feature = "allocator-api2")]
+macro_rules! unlikely {
+    ($condition:expr) => {
+        $condition
+    };
+}
+
+impl<MS: MemorySource> Allocator for MirroredRingBumpAllocator<MS> {
+    #[inline(always)]
+    fn allocate(
+        &self,
+        non_zero_size: NonZeroUsize,
+        non_zero_power_of_two_alignment: NonZeroUsize,
+    ) -> Result<MemoryAddress, AllocError> {
+        debug_assert!(
+            non_zero_power_of_two_alignment <= Self::MAXIMUM_POWER_OF_TWO_ALIGNMENT,
+            "non_zero_power_of_two_alignment `{}` exceeds `{}`",
+            non_zero_power_of_two_alignment,
+            Self::MAXIMUM_POWER_OF_TWO_ALIGNMENT
+        );
+        debug_assert!(
+            non_zero_size.get() <= self.capacity.get(),
+            "non_zero_size `{}` can never fit in a ring buffer of capacity `{}`",
+            non_zero_size,
+            self.capacity
+        );
+
+        let started_at = self.position.get();
+        let candidate = self
+            .base
+            .add(started_at)
+            .round_up_to_power_of_two(non_zero_power_of_two_alignment);
+        let rounded_position = Self::address_of(candidate) - Self::address_of(self.base);
+        let padding = rounded_position - started_at;
+        let size = non_zero_size.get();
+
+        let consumed = padding + size;
+        let new_used = self.used.get() + consumed;
+        if unlikely!(new_used > self.capacity.get()) {
+            return Err(AllocError);
+        }
+
+        self.used.set(new_used);
+        self.position.set((rounded_position + size) % self.capacity.get());
+        self.most_recent_allocation_pointer.set(candidate);
+        self.most_recent_allocation_size.set(size);
+        self.most_recent_allocation_started_at.set(started_at);
+        self.most_recent_allocation_padding.set(padding);
+
+        Ok(candidate)
+    }
+
+    #[inline(always)]
+    fn deallocate(
+        &self,
+        _non_zero_size: NonZeroUsize,
+        _non_zero_power_of_two_alignment: NonZeroUsize,
+        current_memory: MemoryAddress,
+    ) {
+        if unlikely!(current_memory == self.most_recent_allocation_pointer.get()) {
+            let consumed = self.most_recent_allocation_padding.get() + self.most_recent_allocation_size.get();
+            self.used.set(self.used.get() - consumed);
+            self.position.set(self.most_recent_allocation_started_at.get());
+        }
+    }
+
+    #[inline(always)]
+    fn shrinking_reallocate(
+        &self,
+        non_zero_new_size: NonZeroUsize,
+        _non_zero_power_of_two_alignment: NonZeroUsize,
+        _non_zero_current_size: NonZeroUsize,
+        current_memory: MemoryAddress,
+    ) -> Result<MemoryAddress, AllocError> {
+        if unlikely!(current_memory == self.most_recent_allocation_pointer.get()) {
+            let old_size = self.most_recent_allocation_size.get();
+            let new_size = non_zero_new_size.get();
+            self.used.set(self.used.get() - (old_size - new_size));
+            self.most_recent_allocation_size.set(new_size);
+
+            let rounded_position = Self::offset_of(self.base, current_memory, self.capacity);
+            self.position.set((rounded_position + new_size) % self.capacity.get());
+        }
+
+        Ok(current_memory)
+    }
+
+    #[inline(always)]
+    fn growing_reallocate(
+        &self,
+        non_zero_new_size: NonZeroUsize,
+        non_zero_power_of_two_alignment: NonZeroUsize,
+        non_zero_current_size: NonZeroUsize,
+        current_memory: MemoryAddress,
+    ) -> Result<MemoryAddress, AllocError> {
+        if unlikely!(current_memory == self.most_recent_allocation_pointer.get()) {
+            let growth = non_zero_new_size.get() - non_zero_current_size.get();
+            let new_used = self.used.get() + growth;
+
+            if !unlikely!(new_used > self.capacity.get()) {
+                self.used.set(new_used);
+                self.most_recent_allocation_size.set(non_zero_new_size.get());
+
+                let rounded_position = Self::offset_of(self.base, current_memory, self.capacity);
+                self.position
+                    .set((rounded_position + non_zero_new_size.get()) % self.capacity.get());
+
+                return Ok(current_memory);
+            }
+        }
+
+        let result = self.allocate(non_zero_new_size, non_zero_power_of_two_alignment);
+        let pointer: *mut u8 = unsafe { transmute(result) };
+        if unlikely!(pointer.is_null()) {
+            Err(AllocError)
+        } else {
+            let current_size = non_zero_current_size.get();
+            unsafe { pointer.copy_from(current_memory.as_ptr(), current_size) };
+            Ok(unsafe { transmute(pointer) })
+        }
+    }
+}
+
+impl<MS: MemorySource> LocalAllocator for MirroredRingBumpAllocator<MS> {
+    #[inline(always)]
+    fn memory_range(&self) -> MemoryRange {
+        MemoryRange::new(self.base, self.base.add_non_zero(self.capacity))
+    }
+}
+
+impl<MS: MemorySource> MirroredRingBumpAllocator<MS> {
+    const MAXIMUM_POWER_OF_TWO_ALIGNMENT: NonZeroUsize = non_zero_usize(4096);
+
+    /// New instance wrapping a ring buffer of `capacity` bytes, obtained from a `memory_source`
+    /// that maps those bytes twice at consecutive virtual addresses.
+    #[inline(always)]
+    pub fn new(memory_source: MS, capacity: NonZeroUsize) -> Result<Self, AllocError> {
+        let base = memory_source.obtain(capacity)?;
+
+        Ok(Self {
+            most_recent_allocation_pointer: Cell::new(base),
+            most_recent_allocation_size: Cell::new(0),
+            most_recent_allocation_started_at: Cell::new(0),
+            most_recent_allocation_padding: Cell::new(0),
+            position: Cell::new(0),
+            used: Cell::new(0),
+
+            base,
+            capacity,
+            memory_source,
+        })
+    }
+
+    /// Bytes consumed since the last `reset()`, alignment padding included; freeing or shrinking
+    /// the most recent allocation gives its bytes back.
+    #[inline(always)]
+    pub fn used(&self) -> usize {
+        self.used.get()
+    }
+
+    /// Total capacity of the ring buffer.
+    #[inline(always)]
+    pub fn capacity(&self) -> NonZeroUsize {
+        self.capacity
+    }
+
+    /// Makes the whole capacity available again, in O(1), without calling into the
+    /// `MemorySource`.
+    #[inline(always)]
+    pub fn reset(&self) {
+        self.position.set(0);
+        self.used.set(0);
+        self.most_recent_allocation_pointer.set(self.base);
+        self.most_recent_allocation_size.set(0);
+        self.most_recent_allocation_started_at.set(0);
+        self.most_recent_allocation_padding.set(0);
+    }
+
+    #[inline(always)]
+    fn address_of(memory_address: MemoryAddress) -> usize {
+        memory_address.as_ptr() as usize
+    }
+
+    #[inline(always)]
+    fn offset_of(base: MemoryAddress, memory_address: MemoryAddress, capacity: NonZeroUsize) -> usize {
+        (Self::address_of(memory_address) - Self::address_of(base)) % capacity.get()
+    }
+}